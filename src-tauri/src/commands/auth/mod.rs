@@ -0,0 +1,740 @@
+mod providers;
+
+use tauri::{command, Emitter, Window};
+use tauri::Manager;
+use tauri_plugin_oauth::{start_with_config, OauthConfig};
+use tauri_plugin_opener::OpenerExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use dotenvy;
+use std::env;
+use once_cell::sync::Lazy;
+
+use providers::{Provider, UserInfo};
+
+// ===== Config =====
+#[derive(Debug)]
+struct EnvConfig {
+    oauth_ports: Vec<u16>,
+    redirect_path: String,
+    keyring_service: String,
+}
+
+// サーバが待ち受け開始してから、リダイレクトを受け取れずに諦めるまでの秒数
+const PENDING_TIMEOUT_SECS: u64 = 120;
+
+static ENVCONF: Lazy<EnvConfig> = Lazy::new(|| {
+    // Try to load .env files (both default and src-tauri/.env)
+    let _ = dotenvy::from_filename("src-tauri/.env");
+    let _ = dotenvy::dotenv();
+
+    // 先頭から順に試す。既定の候補は末尾が 0 なので、すべて使用中なら OS割当のエフェメラルポートにフォールバックする
+    // （OAUTH_PORTS で指定した場合は、その一覧に 0 を含めたときだけフォールバックする）
+    let oauth_ports = env::var("OAUTH_PORTS")
+        .ok()
+        .map(|s| s.split(',').filter_map(|p| p.trim().parse::<u16>().ok()).collect::<Vec<_>>())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| vec![53682, 53683, 53684, 0]);
+    let redirect_path = env::var("REDIRECT_PATH").unwrap_or_else(|_| "/callback".into());
+    let keyring_service = env::var("KEYRING_SERVICE").unwrap_or_else(|_| "Aquila".into());
+
+    EnvConfig { oauth_ports, redirect_path, keyring_service }
+});
+
+fn client_id_for(provider: &Provider) -> Result<String, String> {
+    match env::var(provider.client_id_env) {
+        Ok(id) => Ok(id),
+        Err(_) => provider
+            .default_client_id
+            .map(|id| id.to_string())
+            .ok_or_else(|| format!("missing client id for provider {} (set {})", provider.id, provider.client_id_env)),
+    }
+}
+
+fn client_secret_for(provider: &Provider) -> Option<String> {
+    provider.client_secret_env.and_then(|k| env::var(k).ok())
+}
+
+fn scopes_for(provider: &Provider) -> String {
+    let env_key = format!("{}_SCOPES", provider.id.to_uppercase());
+    env::var(env_key).unwrap_or_else(|_| provider.default_scopes.to_string())
+}
+
+fn provider_or_err(provider_id: &str) -> Result<Provider, String> {
+    providers::find(provider_id).ok_or_else(|| format!("unknown provider: {}", provider_id))
+}
+
+// ===== 依存: rand, sha2, base64(url-safe no pad), url, reqwest, serde =====
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use url::Url;
+use serde::Deserialize;
+use reqwest::Client;
+use std::sync::{Arc, Mutex};
+
+// ===== PKCE & state =====
+fn gen_code_verifier() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect()
+}
+
+fn code_challenge_s256(verifier: &str) -> String {
+    let digest = Sha256::digest(verifier.as_bytes());
+    URL_SAFE_NO_PAD.encode(digest)
+}
+
+fn gen_state() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+struct PendingOauth {
+    provider: Provider,
+    code_verifier: String,
+    state: String,
+    redirect_uri: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TokenResp {
+    access_token: String,
+    token_type: String, // "Bearer"
+    expires_in: u64,
+    refresh_token: String,
+    scope: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+struct DonePayload {
+    provider: String,
+    token_type: String,
+    scope: String,
+    username: String,
+    avatar_url: String,
+}
+
+// 1回のログイン試行に紐づく状態。pending と handled はセットで take/swap するため、
+// 試行ごとに Arc で包んで新規生成する（static にすると再試行・同時実行で衝突する）
+struct OauthAttempt {
+    pending: Mutex<Option<PendingOauth>>,
+    // この試行のリダイレクトを処理済みかどうか（多重ハンドリング防止）
+    handled: AtomicBool,
+}
+
+// トークンのリフレッシュが重複して走らないようにするロック。
+// (provider_id, account_id) ごとに分けるので、無関係なアカウントのリフレッシュ/読み取りが
+// 互いにブロックされない（INDEX_LOCKS と同じ鍵付けロック方式）
+static REFRESH_LOCKS: Lazy<Mutex<std::collections::HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+fn refresh_lock(provider_id: &str, account_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let key = format!("{}:{}", provider_id, account_id);
+    REFRESH_LOCKS
+        .lock()
+        .unwrap()
+        .entry(key)
+        .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
+        .clone()
+}
+
+// 有効期限切れとみなすまでの猶予（秒）
+const REFRESH_SKEW_SECS: i64 = 60;
+
+async fn get_user_info(provider: &Provider, access_token: &str) -> Result<UserInfo, String> {
+    let cli = Client::new();
+    let resp = cli
+        .get(provider.userinfo_url)
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("failed to get user info: {:?}", resp.text().await.ok()));
+    }
+
+    let body = resp
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    (provider.map_user_info)(&body)
+}
+
+async fn exchange_code_for_token(
+    provider: &Provider,
+    code: &str,
+    redirect_uri: &str,
+    code_verifier: &str,
+) -> Result<TokenResp, String> {
+    let client_id = client_id_for(provider)?;
+    let client_secret = client_secret_for(provider);
+
+    let mut form = vec![
+        ("client_id", client_id.as_str()),
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+        ("code_verifier", code_verifier),
+    ];
+    if let Some(secret) = client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+
+    let cli = Client::new();
+    let resp = cli
+        .post(provider.token_url)
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("token exchange error: {:?}", resp.text().await.ok()));
+    }
+
+    resp.json::<TokenResp>()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+struct StoredTokens {
+    access_token: String,
+    refresh_token: String,
+    token_type: String,
+    scope: String,
+    expires_in: u64,
+    saved_at: i64, // unix epoch seconds
+}
+
+fn account_key(provider_id: &str, account_id: &str) -> String {
+    format!("oauth_tokens:{}:{}", provider_id, account_id)
+}
+
+fn index_key(provider_id: &str) -> String {
+    format!("oauth_tokens:{}:index", provider_id)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+struct AccountIndex {
+    accounts: Vec<String>,
+    active: Option<String>,
+}
+
+fn load_index(provider_id: &str) -> AccountIndex {
+    use keyring::Entry;
+    let key = index_key(provider_id);
+    Entry::new(&ENVCONF.keyring_service, &key)
+        .ok()
+        .and_then(|e| e.get_password().ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(provider_id: &str, idx: &AccountIndex) -> Result<(), String> {
+    use keyring::Entry;
+    let key = index_key(provider_id);
+    let json = serde_json::to_string(idx).map_err(|e| format!("serialize account index: {}", e))?;
+    Entry::new(&ENVCONF.keyring_service, &key)
+        .map_err(|e| format!("keyring new ({}): {}", std::env::consts::OS, e))?
+        .set_password(&json)
+        .map_err(|e| format!("keyring set {} ({}): {}", key, std::env::consts::OS, e))?;
+    Ok(())
+}
+
+fn active_account_id(provider_id: &str) -> Result<String, String> {
+    load_index(provider_id)
+        .active
+        .ok_or_else(|| format!("no active account for provider {}", provider_id))
+}
+
+// プロバイダごとのアカウント索引ロック。load_index → 変更 → save_index が
+// 非アトミックな read-modify-write なので、同じプロバイダへの同時書き込みで
+// どちらかの更新が失われないようにする
+static INDEX_LOCKS: Lazy<Mutex<std::collections::HashMap<String, Arc<Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+fn index_lock(provider_id: &str) -> Arc<Mutex<()>> {
+    INDEX_LOCKS
+        .lock()
+        .unwrap()
+        .entry(provider_id.to_string())
+        .or_insert_with(|| Arc::new(Mutex::new(())))
+        .clone()
+}
+
+fn save_tokens(provider_id: &str, account_id: &str, t: &TokenResp) -> Result<(), String> {
+    use keyring::Entry;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let saved_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+
+    let payload = StoredTokens {
+        access_token: t.access_token.clone(),
+        refresh_token: t.refresh_token.clone(),
+        token_type: t.token_type.clone(),
+        scope: t.scope.clone(),
+        expires_in: t.expires_in,
+        saved_at,
+    };
+
+    let json = serde_json::to_string(&payload)
+        .map_err(|e| format!("serialize tokens: {}", e))?;
+
+    let key = account_key(provider_id, account_id);
+    println!("keyring: saving {}", key);
+    Entry::new(&ENVCONF.keyring_service, &key)
+        .map_err(|e| format!("keyring new ({}): {}", std::env::consts::OS, e))?
+        .set_password(&json)
+        .map_err(|e| format!("keyring set {} ({}): {}", key, std::env::consts::OS, e))?
+        ;
+
+    let _guard = index_lock(provider_id).lock().unwrap();
+    let mut idx = load_index(provider_id);
+    if !idx.accounts.iter().any(|a| a == account_id) {
+        idx.accounts.push(account_id.to_string());
+    }
+    idx.active = Some(account_id.to_string());
+    save_index(provider_id, &idx)
+}
+
+fn load_tokens(provider_id: &str, account_id: &str) -> Result<StoredTokens, String> {
+    use keyring::Entry;
+    let key = account_key(provider_id, account_id);
+    let json = Entry::new(&ENVCONF.keyring_service, &key)
+        .map_err(|e| format!("keyring new ({}): {}", std::env::consts::OS, e))?
+        .get_password()
+        .map_err(|e| format!("keyring get {} ({}): {}", key, std::env::consts::OS, e))?;
+    serde_json::from_str::<StoredTokens>(&json)
+        .map_err(|e| format!("deserialize tokens: {}", e))
+}
+
+fn delete_tokens(provider_id: &str, account_id: &str) -> Result<(), String> {
+    use keyring::Entry;
+    let key = account_key(provider_id, account_id);
+    Entry::new(&ENVCONF.keyring_service, &key)
+        .map_err(|e| format!("keyring new ({}): {}", std::env::consts::OS, e))?
+        .delete_credential()
+        .map_err(|e| format!("keyring delete {} ({}): {}", key, std::env::consts::OS, e))?;
+    Ok(())
+}
+
+fn forget_account(provider_id: &str, account_id: &str) -> Result<(), String> {
+    delete_tokens(provider_id, account_id)?;
+
+    let _guard = index_lock(provider_id).lock().unwrap();
+    let mut idx = load_index(provider_id);
+    idx.accounts.retain(|a| a != account_id);
+    if idx.active.as_deref() == Some(account_id) {
+        idx.active = idx.accounts.first().cloned();
+    }
+    save_index(provider_id, &idx)
+}
+
+async fn revoke_token(provider: &Provider, token: &str, token_type_hint: &str) -> Result<(), String> {
+    let Some(revoke_url) = provider.revoke_url else {
+        return Err(format!("provider {} does not support revocation", provider.id));
+    };
+
+    let form = [
+        ("client_id", client_id_for(provider)?),
+        ("token", token.to_string()),
+        ("token_type_hint", token_type_hint.to_string()),
+    ];
+
+    let cli = Client::new();
+    let resp = cli
+        .post(revoke_url)
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("revoke error: {:?}", resp.text().await.ok()));
+    }
+
+    Ok(())
+}
+
+// =========== Public Commands ==========
+
+/// OAuth ログインを開始（プロバイダを選択可能）
+/// 1) PKCE/state 生成
+/// 2) ローカルサーバを起動（候補ポートを順に試し、redirect_uriは実際に取得したポートから組み立てる）
+/// 3) 認可URLを外部ブラウザで開く
+/// 4) リダイレクト受信 → state検証 → トークン交換 → keyring保存 → イベント通知
+///    （タイムアウトまでにリダイレクトが来なければ `oauth:timeout` を発火して諦める）
+#[command]
+pub async fn start_login(window: Window, provider_id: String) -> Result<u16, String> {
+    let provider = provider_or_err(&provider_id)?;
+
+    // 1) PKCE と state を生成
+    let verifier = gen_code_verifier();
+    let challenge = code_challenge_s256(&verifier);
+    let state = gen_state();
+
+    // この試行専用の pending/handled。サーバ起動直後（実ポート判明後）に pending をセットする
+    let attempt: Arc<OauthAttempt> = Arc::new(OauthAttempt {
+        pending: Mutex::new(None),
+        handled: AtomicBool::new(false),
+    });
+
+    // 2) サーバ起動。候補ポートを順に試す（候補に0番が含まれていれば、それより前がすべて使用中のときOS割当になる）
+    let cfg = OauthConfig {
+        ports: Some(ENVCONF.oauth_ports.clone()),
+        response: Some("You can close this window.".into()),
+    };
+
+    // 先にサーバを立ち上げ（非同期で待受）。handler 内で URL 検証とトークン交換を行う
+    let win_clone = window.clone();
+    let attempt_clone = attempt.clone();
+    let port = start_with_config(cfg, move |url: String| {
+        // 受け取ったリダイレクトURLを検証＆交換は別タスクで（非ブロック）
+        let window = win_clone.clone();
+        let attempt = attempt_clone.clone();
+        tauri::async_runtime::spawn(async move {
+            // リダイレクト多重呼び出しをガード（この試行で最初の1回だけ処理）
+            if attempt.handled.swap(true, Ordering::SeqCst) {
+                let _ = window.emit("oauth:debug", "redirect ignored: already handled");
+                return;
+            }
+
+            // 取り出し（使い捨て）
+            let Some(p) = attempt.pending.lock().unwrap().take() else {
+                let _ = window.emit("oauth:error", "no pending state");
+                return;
+            };
+
+            // URLをパース
+            let parsed = match Url::parse(&url) {
+                Ok(u) => u,
+                Err(e) => {
+                    let _ = window.emit("oauth:error", format!("invalid url: {}", e));
+                    return;
+                }
+            };
+
+            let _ = window.emit("oauth:debug", format!("redirect url received: {}", parsed));
+
+            // host/path チェック（127.0.0.1 と /callback）
+            let host_ok = parsed.host_str() == Some("127.0.0.1");
+            let path_ok = parsed.path() == ENVCONF.redirect_path;
+            if !host_ok || !path_ok {
+                let _ = window.emit("oauth:error", "invalid redirect host/path");
+                return;
+            }
+
+            let qp = parsed.query_pairs();
+            let mut code_opt: Option<String> = None;
+            let mut state_opt: Option<String> = None;
+            for (k, v) in qp {
+                let owned = v.into_owned();
+                    if k == "code" {
+                    code_opt = Some(owned.clone());
+                }
+                if k == "state" {
+                    state_opt = Some(owned);
+                }
+            }
+
+            // state検証
+            if state_opt.as_deref() != Some(&p.state) {
+                let _ = window.emit("oauth:error", "state mismatch");
+                return;
+            }
+
+            let Some(code) = code_opt else {
+                let _ = window.emit("oauth:error", "missing code");
+                return;
+            };
+
+            // トークン交換
+            match exchange_code_for_token(&p.provider, &code, &p.redirect_uri, &p.code_verifier).await {
+                Ok(token) => {
+                    // アカウントを特定するため、保存前にユーザー情報を取得する
+                    let user = match get_user_info(&p.provider, &token.access_token).await {
+                        Ok(user) => user,
+                        Err(e) => {
+                            let _ = window.emit("oauth:error", format!("failed to fetch user info: {}", e));
+                            return;
+                        }
+                    };
+
+                    let _ = window.emit("oauth:debug", "attempting to save tokens to keychain");
+                    if let Err(e) = save_tokens(p.provider.id, &user.id, &token) {
+                        let _ = window.emit("oauth:error", format!("save token error: {}", e));
+                        return;
+                    }
+
+                    let _ = window.emit(
+                        "oauth:done",
+                        DonePayload {
+                            provider: p.provider.id.to_string(),
+                            token_type: token.token_type.clone(),
+                            scope: token.scope.clone(),
+                            username: user.username,
+                            avatar_url: user.avatar_url,
+                        },
+                    );
+                }
+                Err(e) => {
+                    let _ = window.emit("oauth:error", format!("token exchange failed: {}", e));
+                }
+            }
+        });
+    }).map_err(|err| err.to_string())?;
+
+    // 実際にバインドできたポートから redirect_uri を組み立てる
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, ENVCONF.redirect_path);
+
+    *attempt.pending.lock().unwrap() = Some(PendingOauth {
+        provider,
+        code_verifier: verifier.clone(),
+        state: state.clone(),
+        redirect_uri: redirect_uri.clone(),
+    });
+
+    // リダイレクトが一定時間来なければ諦めて pending を破棄し、再試行できるようにする
+    let win_timeout = window.clone();
+    let attempt_timeout = attempt.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(PENDING_TIMEOUT_SECS)).await;
+        let timed_out = attempt_timeout.pending.lock().unwrap().take().is_some();
+        if timed_out {
+            attempt_timeout.handled.store(true, Ordering::SeqCst);
+            let _ = win_timeout.emit("oauth:timeout", ());
+        }
+    });
+
+    // 3) 認可URLを作って外部ブラウザで開く
+    let client_id = client_id_for(&provider)?;
+    let scopes = scopes_for(&provider);
+    let auth_url = Url::parse_with_params(
+        provider.authorize_url,
+        &[
+            ("response_type", "code"),
+            ("client_id", client_id.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("scope", scopes.as_str()),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+            ("state", state.as_str()),
+        ],
+    ).map_err(|e| e.to_string())?.to_string();
+
+    let _ = window.emit("oauth:debug", format!("opening auth url: {}", auth_url));
+
+    // 外部ブラウザで開く（Tauri v2: opener プラグイン）
+    let app = window.app_handle();
+    if let Err(e) = app.opener().open_url(auth_url, None::<String>) {
+        eprintln!("failed to open browser: {}", e);
+    }
+
+    Ok(port)
+}
+
+async fn exchange_refresh_token(provider: &Provider, refresh_token: &str) -> Result<TokenResp, String> {
+    let client_id = client_id_for(provider)?;
+    let client_secret = client_secret_for(provider);
+
+    let mut form = vec![
+        ("client_id", client_id.as_str()),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+    ];
+    if let Some(secret) = client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+
+    let cli = Client::new();
+    let resp = cli
+        .post(provider.token_url)
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("refresh error: {:?}", resp.text().await.ok()));
+    }
+
+    resp.json::<TokenResp>().await.map_err(|e| e.to_string())
+}
+
+/// リフレッシュトークンで更新（アクティブなアカウントに対して実行。必要に応じてフロントからinvoke）
+#[command]
+pub async fn refresh_token(provider_id: String) -> Result<(), String> {
+    let provider = provider_or_err(&provider_id)?;
+    let account_id = active_account_id(&provider_id)?;
+    let _guard = refresh_lock(&provider_id, &account_id).lock().await;
+    let stored = load_tokens(&provider_id, &account_id)?;
+    let token = exchange_refresh_token(&provider, &stored.refresh_token).await?;
+    save_tokens(&provider_id, &account_id, &token)
+}
+
+/// アクティブなアカウントの有効なアクセストークンを「<token_type> <access_token>」形式（例: "Bearer xxx"）で返す
+/// （期限切れが近ければ自動でリフレッシュする）
+#[command]
+pub async fn get_valid_access_token(window: Window, provider_id: String) -> Result<String, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let provider = provider_or_err(&provider_id)?;
+    let account_id = active_account_id(&provider_id)?;
+
+    // 同じアカウントに対して複数回呼ばれてもリフレッシュが重複しないようにロック
+    let _guard = refresh_lock(&provider_id, &account_id).lock().await;
+
+    let stored = load_tokens(&provider_id, &account_id)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let remaining = stored.saved_at + stored.expires_in as i64 - now;
+
+    if remaining < REFRESH_SKEW_SECS {
+        let token = exchange_refresh_token(&provider, &stored.refresh_token).await?;
+        save_tokens(&provider_id, &account_id, &token)?;
+        let _ = window.emit("oauth:refreshed", &provider_id);
+        Ok(format!("{} {}", token.token_type, token.access_token))
+    } else {
+        Ok(format!("{} {}", stored.token_type, stored.access_token))
+    }
+}
+
+/// アクティブなアカウントのトークンを失効させ、keyringから削除する（完全ログアウト）
+#[command]
+pub async fn logout(window: Window, provider_id: String) -> Result<(), String> {
+    let provider = provider_or_err(&provider_id)?;
+    let account_id = active_account_id(&provider_id)?;
+    let stored = load_tokens(&provider_id, &account_id)?;
+
+    // 失効はベストエフォート：サーバ側が失敗してもローカルのセッションは必ず片付ける
+    if let Err(e) = revoke_token(&provider, &stored.access_token, "access_token").await {
+        let _ = window.emit("oauth:debug", format!("revoke access_token failed: {}", e));
+    }
+    if let Err(e) = revoke_token(&provider, &stored.refresh_token, "refresh_token").await {
+        let _ = window.emit("oauth:debug", format!("revoke refresh_token failed: {}", e));
+    }
+
+    forget_account(&provider_id, &account_id)?;
+
+    let _ = window.emit("oauth:logout", &provider_id);
+
+    Ok(())
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct AccountsList {
+    accounts: Vec<String>,
+    active: Option<String>,
+}
+
+/// 既知のアカウントID一覧とアクティブなアカウントIDを返す
+#[command]
+pub fn list_accounts(provider_id: String) -> Result<AccountsList, String> {
+    provider_or_err(&provider_id)?;
+    let idx = load_index(&provider_id);
+    Ok(AccountsList { accounts: idx.accounts, active: idx.active })
+}
+
+/// アクティブなアカウントを切り替える
+#[command]
+pub fn switch_account(provider_id: String, account_id: String) -> Result<(), String> {
+    provider_or_err(&provider_id)?;
+    let _guard = index_lock(&provider_id).lock().unwrap();
+    let mut idx = load_index(&provider_id);
+    if !idx.accounts.iter().any(|a| a == &account_id) {
+        return Err(format!("unknown account: {}", account_id));
+    }
+    idx.active = Some(account_id);
+    save_index(&provider_id, &idx)
+}
+
+/// アカウントをローカルのストレージから取り除く（トークンの失効は行わない。
+/// 失効と削除をまとめて行うのは logout で、その対象はアクティブなアカウントのみ）
+#[command]
+pub fn remove_account(provider_id: String, account_id: String) -> Result<(), String> {
+    provider_or_err(&provider_id)?;
+    let idx = load_index(&provider_id);
+    if !idx.accounts.iter().any(|a| a == &account_id) {
+        return Err(format!("unknown account: {}", account_id));
+    }
+    forget_account(&provider_id, &account_id)
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
+#[serde(tag = "status")]
+pub enum SessionStatus {
+    Valid { expires_at: String, scopes: Vec<String> },
+    Expired,
+    ScopeMismatch { granted: Vec<String>, missing: Vec<String> },
+    Unauthenticated,
+}
+
+#[derive(Deserialize, Debug)]
+struct IntrospectResp {
+    expires: String,
+    scopes: Vec<String>,
+}
+
+/// アクティブなアカウントのトークンが、プロバイダ側で見てもまだ有効かどうかを確認する
+#[command]
+pub async fn check_session(provider_id: String) -> Result<SessionStatus, String> {
+    let provider = provider_or_err(&provider_id)?;
+    let Some(introspect_url) = provider.introspect_url else {
+        return Err(format!("provider {} does not support session introspection", provider.id));
+    };
+
+    let Ok(account_id) = active_account_id(&provider_id) else {
+        return Ok(SessionStatus::Unauthenticated);
+    };
+    let Ok(stored) = load_tokens(&provider_id, &account_id) else {
+        return Ok(SessionStatus::Unauthenticated);
+    };
+
+    let cli = Client::new();
+    let resp = cli
+        .get(introspect_url)
+        .bearer_auth(&stored.access_token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Ok(SessionStatus::Expired);
+    }
+    if !resp.status().is_success() {
+        return Err(format!("session check error: {:?}", resp.text().await.ok()));
+    }
+
+    let info: IntrospectResp = resp.json().await.map_err(|e| e.to_string())?;
+
+    let required: Vec<String> = scopes_for(&provider)
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect();
+    let missing: Vec<String> = required
+        .into_iter()
+        .filter(|s| !info.scopes.contains(s))
+        .collect();
+
+    if !missing.is_empty() {
+        return Ok(SessionStatus::ScopeMismatch { granted: info.scopes, missing });
+    }
+
+    Ok(SessionStatus::Valid { expires_at: info.expires, scopes: info.scopes })
+}
@@ -0,0 +1,113 @@
+use serde_json::Value;
+
+/// 共通ユーザー情報（各プロバイダのレスポンス形式を正規化したもの）
+#[derive(Debug, Clone)]
+pub struct UserInfo {
+    pub id: String,
+    pub username: String,
+    pub avatar_url: String,
+}
+
+/// OAuthプロバイダごとのエンドポイント/スコープ/ユーザー情報マッピング
+#[derive(Debug, Clone, Copy)]
+pub struct Provider {
+    pub id: &'static str,
+    pub authorize_url: &'static str,
+    pub token_url: &'static str,
+    pub userinfo_url: &'static str,
+    pub revoke_url: Option<&'static str>,
+    pub introspect_url: Option<&'static str>,
+    pub default_scopes: &'static str,
+    pub client_id_env: &'static str,
+    pub default_client_id: Option<&'static str>,
+    pub client_secret_env: Option<&'static str>,
+    pub map_user_info: fn(&Value) -> Result<UserInfo, String>,
+}
+
+fn map_discord(v: &Value) -> Result<UserInfo, String> {
+    let id = v.get("id").and_then(Value::as_str).ok_or("missing id")?.to_string();
+    let username = v
+        .get("username")
+        .and_then(Value::as_str)
+        .ok_or("missing username")?
+        .to_string();
+    let avatar = v.get("avatar").and_then(Value::as_str).unwrap_or_default();
+    let avatar_url = format!("https://cdn.discordapp.com/avatars/{}/{}.png", id, avatar);
+    Ok(UserInfo { id, username, avatar_url })
+}
+
+fn map_google(v: &Value) -> Result<UserInfo, String> {
+    let id = v.get("sub").and_then(Value::as_str).ok_or("missing sub")?.to_string();
+    let username = v.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+    let avatar_url = v.get("picture").and_then(Value::as_str).unwrap_or_default().to_string();
+    Ok(UserInfo { id, username, avatar_url })
+}
+
+fn map_slack(v: &Value) -> Result<UserInfo, String> {
+    let id = v
+        .get("user_id")
+        .and_then(Value::as_str)
+        .ok_or("missing user_id")?
+        .to_string();
+    let username = v
+        .get("user")
+        .and_then(|u| u.get("name"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let avatar_url = v
+        .get("user")
+        .and_then(|u| u.get("image_192"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Ok(UserInfo { id, username, avatar_url })
+}
+
+pub const DISCORD: Provider = Provider {
+    id: "discord",
+    authorize_url: "https://discord.com/api/oauth2/authorize",
+    token_url: "https://discord.com/api/oauth2/token",
+    userinfo_url: "https://discord.com/api/users/@me",
+    revoke_url: Some("https://discord.com/api/oauth2/token/revoke"),
+    introspect_url: Some("https://discord.com/api/oauth2/@me"),
+    default_scopes: "identify email",
+    client_id_env: "DISCORD_CLIENT_ID",
+    default_client_id: Some("1398967218842108006"),
+    client_secret_env: None,
+    map_user_info: map_discord,
+};
+
+pub const GOOGLE: Provider = Provider {
+    id: "google",
+    authorize_url: "https://accounts.google.com/o/oauth2/v2/auth",
+    token_url: "https://oauth2.googleapis.com/token",
+    userinfo_url: "https://openidconnect.googleapis.com/v1/userinfo",
+    revoke_url: Some("https://oauth2.googleapis.com/revoke"),
+    introspect_url: None,
+    default_scopes: "openid email profile",
+    client_id_env: "GOOGLE_CLIENT_ID",
+    default_client_id: None,
+    client_secret_env: Some("GOOGLE_CLIENT_SECRET"),
+    map_user_info: map_google,
+};
+
+pub const SLACK: Provider = Provider {
+    id: "slack",
+    authorize_url: "https://slack.com/oauth/v2/authorize",
+    token_url: "https://slack.com/api/oauth.v2.access",
+    userinfo_url: "https://slack.com/api/openid.connect.userInfo",
+    revoke_url: Some("https://slack.com/api/auth.revoke"),
+    introspect_url: None,
+    default_scopes: "identity.basic identity.email",
+    client_id_env: "SLACK_CLIENT_ID",
+    default_client_id: None,
+    client_secret_env: Some("SLACK_CLIENT_SECRET"),
+    map_user_info: map_slack,
+};
+
+pub const ALL: &[Provider] = &[DISCORD, GOOGLE, SLACK];
+
+pub fn find(id: &str) -> Option<Provider> {
+    ALL.iter().copied().find(|p| p.id == id)
+}